@@ -0,0 +1,164 @@
+use actix_web::{dev::Payload, error::ErrorUnauthorized, Error as ActixError, FromRequest, HttpRequest};
+use chrono::Utc;
+use futures_util::future::{ready, Ready};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use std::env;
+
+use crate::error::Error;
+
+/// Role levels carried in `TokenClaims::role`. Kept in one place so the
+/// eligibility and status flows reference the same values as the auth guard.
+pub const STUDENT: i16 = 0;
+pub const ADMIN: i16 = 1;
+
+/// Claims embedded in the JWT issued on a successful login.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TokenClaims {
+    pub sub: String, // student_id
+    pub role: i16,
+    pub exp: usize,
+    pub iat: usize,
+}
+
+/// Reads `JWT_SECRET` and `JWT_MAXAGE` (seconds) from the environment.
+fn jwt_secret() -> String {
+    env::var("JWT_SECRET").expect("JWT_SECRET is not set in the .env file. Please configure it.")
+}
+
+fn jwt_maxage() -> i64 {
+    env::var("JWT_MAXAGE")
+        .expect("JWT_MAXAGE is not set in the .env file. Please configure it.")
+        .parse()
+        .expect("JWT_MAXAGE must be an integer number of seconds")
+}
+
+/// Signs a `TokenClaims` for the given student/role pair and returns the JWT.
+pub fn issue_token(student_id: &str, role: i16) -> Result<String, jsonwebtoken::errors::Error> {
+    let now = Utc::now();
+    let iat = now.timestamp() as usize;
+    let exp = (now.timestamp() + jwt_maxage()) as usize;
+
+    let claims = TokenClaims {
+        sub: student_id.to_owned(),
+        role,
+        exp,
+        iat,
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(jwt_secret().as_bytes()),
+    )
+}
+
+/// An authenticated request, extracted from the `Authorization: Bearer <jwt>` header.
+///
+/// Handlers that take `AuthUser` as an argument are unreachable without a valid,
+/// unexpired token; failing extraction short-circuits the request with a 401.
+#[derive(Debug, Clone)]
+pub struct AuthUser {
+    pub student_id: String,
+    pub role: i16,
+}
+
+impl AuthUser {
+    /// Returns `Error::Forbidden` unless this user's role meets `required`.
+    pub fn require_role(&self, required: i16) -> Result<(), Error> {
+        if self.role >= required {
+            Ok(())
+        } else {
+            Err(Error::Forbidden)
+        }
+    }
+}
+
+impl FromRequest for AuthUser {
+    type Error = ActixError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let token = req
+            .headers()
+            .get("Authorization")
+            .and_then(|header| header.to_str().ok())
+            .and_then(|header| header.strip_prefix("Bearer "));
+
+        let token = match token {
+            Some(token) => token,
+            None => return ready(Err(ErrorUnauthorized("Missing or malformed Authorization header"))),
+        };
+
+        let claims = decode::<TokenClaims>(
+            token,
+            &DecodingKey::from_secret(jwt_secret().as_bytes()),
+            &Validation::default(),
+        );
+
+        match claims {
+            Ok(data) => ready(Ok(AuthUser {
+                student_id: data.claims.sub,
+                role: data.claims.role,
+            })),
+            Err(_) => ready(Err(ErrorUnauthorized("Invalid or expired token"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_test_env() {
+        env::set_var("JWT_SECRET", "test-secret");
+        env::set_var("JWT_MAXAGE", "3600");
+    }
+
+    #[test]
+    fn issue_token_round_trips_claims() {
+        set_test_env();
+
+        let token = issue_token("student-1", ADMIN).expect("token should be issued");
+        let data = decode::<TokenClaims>(
+            &token,
+            &DecodingKey::from_secret(jwt_secret().as_bytes()),
+            &Validation::default(),
+        )
+        .expect("token should decode with the signing secret");
+
+        assert_eq!(data.claims.sub, "student-1");
+        assert_eq!(data.claims.role, ADMIN);
+        assert!(data.claims.exp > data.claims.iat);
+    }
+
+    #[test]
+    fn issue_token_rejects_under_wrong_secret() {
+        set_test_env();
+
+        let token = issue_token("student-1", STUDENT).expect("token should be issued");
+        let wrong_key = DecodingKey::from_secret(b"not-the-real-secret");
+
+        assert!(decode::<TokenClaims>(&token, &wrong_key, &Validation::default()).is_err());
+    }
+
+    #[test]
+    fn require_role_allows_exact_threshold() {
+        let user = AuthUser {
+            student_id: "student-1".to_owned(),
+            role: ADMIN,
+        };
+
+        assert!(user.require_role(ADMIN).is_ok());
+    }
+
+    #[test]
+    fn require_role_rejects_below_threshold() {
+        let user = AuthUser {
+            student_id: "student-1".to_owned(),
+            role: STUDENT,
+        };
+
+        assert!(matches!(user.require_role(ADMIN), Err(Error::Forbidden)));
+    }
+}
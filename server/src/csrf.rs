@@ -0,0 +1,302 @@
+use actix_web::{
+    body::EitherBody,
+    cookie::{Cookie, SameSite},
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::{
+        header::{HeaderName, HeaderValue},
+        Method,
+    },
+    Error as ActixError, HttpResponse,
+};
+use futures_util::future::{ok, LocalBoxFuture, Ready};
+use rand::{distributions::Alphanumeric, thread_rng, Rng};
+use std::env;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+const DEFAULT_COOKIE_NAME: &str = "Csrf-Token";
+const DEFAULT_HEADER_NAME: &str = "X-CSRF-Token";
+
+/// Browsers default an attribute-less cookie to `SameSite=Lax`, which is
+/// never sent on a cross-site `fetch`/XHR — so a credentialed cross-origin
+/// frontend needs `SameSite=None`, which in turn requires `Secure`. Defaults
+/// to `true`; set `CSRF_COOKIE_SECURE=false` for plain-HTTP local dev, which
+/// falls back to `SameSite=Lax` since `None` without `Secure` is rejected.
+fn default_secure() -> bool {
+    env::var("CSRF_COOKIE_SECURE")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(true)
+}
+
+fn generate_token() -> String {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
+}
+
+/// Double-submit-cookie CSRF guard for state-changing routes.
+///
+/// On a safe (non-protected) request it issues a `Csrf-Token` cookie and
+/// echoes it in a response header; on a protected request it requires the
+/// cookie and the `X-CSRF-Token` header to match. Requests authenticated
+/// with a `Bearer` token are exempt, since they aren't cookie-based and
+/// can't be forged by a third-party site.
+pub struct Csrf {
+    cookie_name: String,
+    header_name: String,
+    protected_methods: Vec<Method>,
+    secure: bool,
+}
+
+impl Csrf {
+    pub fn new() -> Self {
+        Self {
+            cookie_name: DEFAULT_COOKIE_NAME.to_owned(),
+            header_name: DEFAULT_HEADER_NAME.to_owned(),
+            protected_methods: vec![Method::POST, Method::PUT, Method::PATCH, Method::DELETE],
+            secure: default_secure(),
+        }
+    }
+
+    pub fn cookie_name(mut self, name: impl Into<String>) -> Self {
+        self.cookie_name = name.into();
+        self
+    }
+
+    pub fn header_name(mut self, name: impl Into<String>) -> Self {
+        self.header_name = name.into();
+        self
+    }
+
+    pub fn protected_methods(mut self, methods: Vec<Method>) -> Self {
+        self.protected_methods = methods;
+        self
+    }
+
+    /// Overrides whether the CSRF cookie is marked `Secure` (and therefore
+    /// `SameSite=None` rather than `Lax`). See `default_secure`.
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+}
+
+impl Default for Csrf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for Csrf
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = ActixError> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = ActixError;
+    type Transform = CsrfMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(CsrfMiddleware {
+            service: Rc::new(service),
+            cookie_name: self.cookie_name.clone(),
+            header_name: self.header_name.clone(),
+            protected_methods: self.protected_methods.clone(),
+            secure: self.secure,
+        })
+    }
+}
+
+pub struct CsrfMiddleware<S> {
+    service: Rc<S>,
+    cookie_name: String,
+    header_name: String,
+    protected_methods: Vec<Method>,
+    secure: bool,
+}
+
+impl<S, B> Service<ServiceRequest> for CsrfMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = ActixError> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = ActixError;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let is_bearer_request = req
+            .headers()
+            .get("Authorization")
+            .and_then(|header| header.to_str().ok())
+            .map(|header| header.starts_with("Bearer "))
+            .unwrap_or(false);
+
+        if !is_bearer_request && self.protected_methods.contains(req.method()) {
+            let cookie_token = req
+                .cookie(&self.cookie_name)
+                .map(|cookie| cookie.value().to_owned());
+            let header_token = req
+                .headers()
+                .get(self.header_name.as_str())
+                .and_then(|header| header.to_str().ok())
+                .map(str::to_owned);
+
+            let tokens_match = matches!((cookie_token, header_token), (Some(a), Some(b)) if a == b);
+
+            if !tokens_match {
+                let response = HttpResponse::Forbidden()
+                    .json(serde_json::json!({
+                        "status": "error",
+                        "message": "Invalid or missing CSRF token"
+                    }))
+                    .map_into_right_body();
+                return Box::pin(async move { Ok(req.into_response(response)) });
+            }
+        }
+
+        let service = Rc::clone(&self.service);
+        let cookie_name = self.cookie_name.clone();
+        let header_name = self.header_name.clone();
+        let secure = self.secure;
+
+        Box::pin(async move {
+            let mut res = service.call(req).await?.map_into_left_body();
+
+            let token = generate_token();
+            if let Ok(header_value) = HeaderValue::from_str(&token) {
+                res.headers_mut()
+                    .insert(HeaderName::from_bytes(header_name.as_bytes()).unwrap(), header_value);
+            }
+            // SameSite=None is required for the cookie to be sent on a cross-origin
+            // request, but browsers reject None without Secure — fall back to Lax
+            // for plain-HTTP local dev where Secure can't be set.
+            let same_site = if secure { SameSite::None } else { SameSite::Lax };
+            let cookie = Cookie::build(cookie_name, token)
+                .path("/")
+                .same_site(same_site)
+                .secure(secure)
+                .finish();
+            let _ = res.response_mut().add_cookie(&cookie);
+
+            Ok(res)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http::StatusCode, test, web, App};
+
+    fn extract_token(res: &actix_web::dev::ServiceResponse<impl actix_web::body::MessageBody>) -> (String, String) {
+        let cookie = res
+            .response()
+            .cookies()
+            .find(|cookie| cookie.name() == DEFAULT_COOKIE_NAME)
+            .expect("CSRF cookie should be set");
+        let header = res
+            .headers()
+            .get(DEFAULT_HEADER_NAME)
+            .expect("CSRF header should be set")
+            .to_str()
+            .unwrap()
+            .to_owned();
+
+        (cookie.value().to_owned(), header)
+    }
+
+    #[actix_web::test]
+    async fn safe_request_sets_cross_origin_ready_cookie() {
+        let app = test::init_service(
+            App::new()
+                .wrap(Csrf::new())
+                .route("/", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let res = test::call_service(&app, test::TestRequest::get().uri("/").to_request()).await;
+
+        let cookie = res
+            .response()
+            .cookies()
+            .find(|cookie| cookie.name() == DEFAULT_COOKIE_NAME)
+            .expect("CSRF cookie should be set");
+
+        // A cross-origin fetch() only carries this cookie back if SameSite=None
+        // (which browsers require to be paired with Secure).
+        assert_eq!(cookie.same_site(), Some(SameSite::None));
+        assert!(cookie.secure().unwrap_or(false));
+    }
+
+    #[actix_web::test]
+    async fn matching_cookie_and_header_is_accepted() {
+        let app = test::init_service(
+            App::new()
+                .wrap(Csrf::new())
+                .route("/", web::get().to(HttpResponse::Ok))
+                .route("/", web::post().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let bootstrap = test::call_service(&app, test::TestRequest::get().uri("/").to_request()).await;
+        let (cookie_value, header_value) = extract_token(&bootstrap);
+
+        let req = test::TestRequest::post()
+            .uri("/")
+            .cookie(Cookie::new(DEFAULT_COOKIE_NAME, cookie_value))
+            .insert_header((DEFAULT_HEADER_NAME, header_value))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn mismatched_cookie_and_header_is_rejected() {
+        let app = test::init_service(
+            App::new()
+                .wrap(Csrf::new())
+                .route("/", web::get().to(HttpResponse::Ok))
+                .route("/", web::post().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let bootstrap = test::call_service(&app, test::TestRequest::get().uri("/").to_request()).await;
+        let (cookie_value, _) = extract_token(&bootstrap);
+
+        let req = test::TestRequest::post()
+            .uri("/")
+            .cookie(Cookie::new(DEFAULT_COOKIE_NAME, cookie_value))
+            .insert_header((DEFAULT_HEADER_NAME, "not-the-same-token"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[actix_web::test]
+    async fn bearer_authenticated_post_is_exempt_without_csrf_tokens() {
+        let app = test::init_service(
+            App::new()
+                .wrap(Csrf::new())
+                .route("/", web::post().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/")
+            .insert_header(("Authorization", "Bearer some.jwt.token"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+}
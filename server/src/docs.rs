@@ -0,0 +1,37 @@
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+/// Assembles the OpenAPI document for the scholarship API, served at
+/// `/api-docs/openapi.json` and rendered by the Swagger UI at `/swagger-ui`.
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        crate::csrf_token,
+        crate::submit_form,
+        crate::login,
+        crate::get_applicants,
+        crate::update_applicant_status,
+    ),
+    components(schemas(
+        crate::FormData,
+        crate::LoginData,
+        crate::Response,
+        crate::UpdateStatusData,
+    )),
+    modifiers(&SecurityAddon)
+)]
+pub struct ApiDoc;
+
+/// Registers the `bearer_token` scheme referenced by `#[utoipa::path(security(...))]`
+/// on the admin-gated endpoints, so Swagger UI shows an "Authorize" button.
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_token",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).bearer_format("JWT").build()),
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use serde_json::json;
+
+/// Crate-wide error type. Every fallible handler returns `Result<HttpResponse, Error>`
+/// so failures flow through `?` instead of being hand-matched in each handler.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("a database error occurred")]
+    Sqlx(sqlx::Error),
+
+    #[error("an account with this email already exists")]
+    EmailExists,
+
+    #[error("invalid email or password")]
+    InvalidCredentials,
+
+    #[error("you do not have permission to perform this action")]
+    Forbidden,
+
+    #[error("a password hashing error occurred")]
+    Bcrypt(#[from] bcrypt::BcryptError),
+
+    #[error("a password hashing error occurred")]
+    Argon2(#[from] argon2::password_hash::Error),
+
+    #[error("a token error occurred")]
+    Jwt(#[from] jsonwebtoken::errors::Error),
+}
+
+/// Converts a raw `sqlx::Error` into our error type, promoting a unique-violation
+/// specifically on `form_data`'s email index to `Error::EmailExists`. A violation
+/// of any other unique constraint (e.g. `student_id`) falls through to a generic
+/// `Error::Sqlx` 500 instead of being mislabeled as a duplicate email.
+impl From<sqlx::Error> for Error {
+    fn from(error: sqlx::Error) -> Self {
+        if let sqlx::Error::Database(ref db_err) = error {
+            let violates_email_constraint = db_err.is_unique_violation()
+                && db_err
+                    .constraint()
+                    // SQLite reports no constraint name; its message reads
+                    // "UNIQUE constraint failed: form_data.email".
+                    .unwrap_or_else(|| db_err.message())
+                    .contains("email");
+
+            if violates_email_constraint {
+                return Error::EmailExists;
+            }
+        }
+
+        Error::Sqlx(error)
+    }
+}
+
+impl ResponseError for Error {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Error::Sqlx(_) | Error::Bcrypt(_) | Error::Argon2(_) | Error::Jwt(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            Error::EmailExists => StatusCode::CONFLICT,
+            Error::InvalidCredentials => StatusCode::UNAUTHORIZED,
+            Error::Forbidden => StatusCode::FORBIDDEN,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        if let Error::Sqlx(error) = self {
+            eprintln!("Database error: {:?}", error);
+        }
+
+        HttpResponse::build(self.status_code()).json(json!({
+            "status": "error",
+            "message": self.to_string()
+        }))
+    }
+}
@@ -1,14 +1,26 @@
-use actix_web::{web, App, HttpServer, Responder, HttpResponse};
+use actix_web::{web, App, HttpServer, HttpResponse};
 use actix_cors::Cors;
 use serde::{Deserialize, Serialize};
 use sqlx::{SqlitePool, query, query_as};
 use dotenvy::dotenv;
 use std::{env, string};
-use bcrypt::{hash, verify, DEFAULT_COST};
-use chrono::{NaiveDate, Utc}; 
+use chrono::{NaiveDate, Utc};
 use chrono::Datelike;
+use utoipa::{OpenApi, ToSchema};
+use utoipa_swagger_ui::SwaggerUi;
 
-#[derive(Serialize, Deserialize, Debug)]
+mod auth;
+mod csrf;
+mod docs;
+mod error;
+mod password;
+use auth::{issue_token, AuthUser};
+use csrf::Csrf;
+use docs::ApiDoc;
+use error::Error;
+use password::{hash_password, verify_password, Verified};
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 struct FormData {
     first_name: String,
     last_name: String,
@@ -26,26 +38,19 @@ struct FormData {
 
 
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 struct UpdateStatusData {
     student_id: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 struct LoginData {
     email: String,
     password: Option<String>,
 }
 
 
-#[derive(Serialize, Deserialize, Debug)]
-struct UserData {
-    email: String,
-    status: Option<i64>,
-    role : Option<i64>
-}
-
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 struct Response {
     first_name: String,
     last_name: String,
@@ -88,65 +93,86 @@ fn calculate_eligibility(gpa: f32, credit_hours: i32, age: i32) -> i32 {
 }
 
 
+/// Public bootstrap route: an anonymous visitor hits this to receive the
+/// initial `Csrf-Token` cookie/header pair before calling `/submit` or `/login`.
+#[utoipa::path(
+    get,
+    path = "/csrf-token",
+    responses(
+        (status = 200, description = "CSRF cookie and header issued")
+    )
+)]
+async fn csrf_token() -> HttpResponse {
+    HttpResponse::Ok().json(serde_json::json!({ "status": "ok" }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/applicant/update-status",
+    request_body = UpdateStatusData,
+    responses(
+        (status = 200, description = "Applicant status updated to accepted"),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 500, description = "Database error")
+    ),
+    security(("bearer_token" = []))
+)]
 async fn update_applicant_status(
-    status_data: web::Json<UpdateStatusData>, 
+    user: AuthUser,
+    status_data: web::Json<UpdateStatusData>,
     db_pool: web::Data<SqlitePool>
-) -> impl Responder {
+) -> Result<HttpResponse, Error> {
+    user.require_role(auth::ADMIN)?;
+
     let student_id = &status_data.student_id;
 
     // Update the applicant's status to 2 (Accepted)
-    match query!(
+    query!(
         r#"
-        UPDATE form_data 
+        UPDATE form_data
         SET status = 2
         WHERE student_id = ?
         "#,
         student_id
     )
     .execute(db_pool.get_ref())
-    .await 
-    {
-        Ok(_) => HttpResponse::Ok().json({
-            serde_json::json!({
-                "status": "success",
-                "message": "Applicant status updated to accepted"
-            })
-        }),
-        Err(error) => {
-            eprintln!("Error updating applicant status: {:?}", error);
-            HttpResponse::InternalServerError().json({
-                serde_json::json!({
-                    "status": "error",
-                    "message": "Failed to update applicant status. Please try again later."
-                })
-            })
-        }
-    }
+    .await?;
+
+    Ok(HttpResponse::Ok().json({
+        serde_json::json!({
+            "status": "success",
+            "message": "Applicant status updated to accepted"
+        })
+    }))
 }
 
 /// Handles form submissions and saves them to the SQLite database
-async fn submit_form(form: web::Json<FormData>, db_pool: web::Data<SqlitePool>) -> impl Responder {
+#[utoipa::path(
+    post,
+    path = "/submit",
+    request_body = FormData,
+    responses(
+        (status = 200, description = "Form submitted successfully"),
+        (status = 409, description = "An account with this email already exists"),
+        (status = 500, description = "Database or hashing error")
+    )
+)]
+async fn submit_form(form: web::Json<FormData>, db_pool: web::Data<SqlitePool>) -> Result<HttpResponse, Error> {
     let mut form_data = form.into_inner();
 
-    let hashed_password = match hash(&form_data.password.unwrap_or_default(), DEFAULT_COST) {
-        Ok(hashed) => hashed,
-        Err(error) => {
-            eprintln!("Error hashing password: {:?}", error);
-            return HttpResponse::InternalServerError().json({
-                serde_json::json!({
-                    "status": "error",
-                    "message": "Failed to process form due to a server error"
-                })
-            });
-        }
-    };
+    // `/submit` is unauthenticated, so the client-supplied role can't be trusted —
+    // every applicant is created as a STUDENT; admins are promoted out-of-band.
+    form_data.role = auth::STUDENT;
+
+    let hashed_password = hash_password(&form_data.password.unwrap_or_default())?;
     form_data.password = Some(hashed_password);
     let age = calculate_age(&form_data.dob).unwrap_or(0);
     let eligibility_status = calculate_eligibility(form_data.gpa, form_data.total_credits, age);
-    match query!(
+    let result = query!(
         r#"
-        INSERT INTO form_data 
-        (first_name, last_name, student_id, gender, dob, college_year, total_credits, phone_number, email, password, status, role, gpa) 
+        INSERT INTO form_data
+        (first_name, last_name, student_id, gender, dob, college_year, total_credits, phone_number, email, password, status, role, gpa)
         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
         form_data.first_name,
@@ -159,154 +185,119 @@ async fn submit_form(form: web::Json<FormData>, db_pool: web::Data<SqlitePool>)
         form_data.phone_number,
         form_data.email,
         form_data.password,
-        eligibility_status, 
+        eligibility_status,
         form_data.role,
         form_data.gpa,
     )
     .execute(db_pool.get_ref())
-    .await 
-    {
-        Ok(result) => {
-            HttpResponse::Ok().json({
-                serde_json::json!({
-                    "status": "success",
-                    "message": "Form submitted successfully",
-                    "data_id": result.last_insert_rowid()
-                })
-            })
-        },
-        Err(error) => {
-            eprintln!("Error inserting form data: {:?}", error);
-            HttpResponse::InternalServerError().json({
-                serde_json::json!({
-                    "status": "error",
-                    "message": "Failed to submit form. Please try again later."
-                })
-            })
-        }
-    }
+    .await?;
+
+    Ok(HttpResponse::Ok().json({
+        serde_json::json!({
+            "status": "success",
+            "message": "Form submitted successfully",
+            "data_id": result.last_insert_rowid()
+        })
+    }))
 }
 
 /// Handles login requests and checks if the user's email and password match the database record
-async fn login(form: web::Json<LoginData>, db_pool: web::Data<SqlitePool>) -> impl Responder {
+#[utoipa::path(
+    post,
+    path = "/login",
+    request_body = LoginData,
+    responses(
+        (status = 200, description = "Login successful, returns a bearer token"),
+        (status = 401, description = "Invalid email or password"),
+        (status = 500, description = "Database or token-signing error")
+    )
+)]
+async fn login(form: web::Json<LoginData>, db_pool: web::Data<SqlitePool>) -> Result<HttpResponse, Error> {
     let login_data = form.into_inner();
 
-    match query!(
+    let user = query!(
         r#"
-        SELECT email, password, status, role
-        FROM form_data 
+        SELECT student_id, email, password, status, role
+        FROM form_data
         WHERE email = ?
         "#,
         login_data.email
     )
     .fetch_optional(db_pool.get_ref())
-    .await 
-    {
-        Ok(Some(user)) => {
-
-            let is_valid_password = if let (Some(login_password), Some(user_password)) = (login_data.password, user.password) {
-                match verify(&login_password, &user_password) {
-                    Ok(valid) => valid,
-                    Err(error) => {
-                        eprintln!("Error verifying password: {:?}", error);
-                        return HttpResponse::InternalServerError().json({
-                            serde_json::json!({
-                                "status": "error",
-                                "message": "Internal server error"
-                            })
-                        });
-                    }
-                }
-            } else {
-                return HttpResponse::Unauthorized().json({
-                    serde_json::json!({
-                        "status": "error",
-                        "message": "Invalid email or password"
-                    })
-                });
-            };
-
-            if is_valid_password {
-                let user_data = UserData {
-                    email: user.email,
-                    status: Some(user.status),
-                    role : Some(user.role)
-                };
-
-                HttpResponse::Ok().json({
-                    serde_json::json!({
-                        "status": "success",
-                        "message": "Login successful",
-                        "user": user_data
-                    })
-                })
-            } else {
-                HttpResponse::Unauthorized().json({
-                    serde_json::json!({
-                        "status": "error",
-                        "message": "Invalid email or password"
-                    })
-                })
-            }
+    .await?
+    .ok_or(Error::InvalidCredentials)?;
+
+    let rehash = match (login_data.password, &user.password) {
+        (Some(login_password), Some(user_password)) => match verify_password(&login_password, user_password)? {
+            Verified::Valid { rehash } => rehash,
+            Verified::Invalid => return Err(Error::InvalidCredentials),
         },
-        Ok(None) => HttpResponse::Unauthorized().json({
-            serde_json::json!({
-                "status": "error",
-                "message": "Invalid email or password"
-            })
-        }),
-        Err(error) => {
-            eprintln!("Error querying login data: {:?}", error);
-            HttpResponse::InternalServerError().json({
-                serde_json::json!({
-                    "status": "error",
-                    "message": "Internal server error"
-                })
-            })
-        }
+        _ => return Err(Error::InvalidCredentials),
+    };
+
+    // Legacy bcrypt accounts are transparently upgraded to Argon2id on next login.
+    if let Some(new_hash) = rehash {
+        query!(
+            "UPDATE form_data SET password = ? WHERE student_id = ?",
+            new_hash,
+            user.student_id
+        )
+        .execute(db_pool.get_ref())
+        .await?;
     }
+
+    let token = issue_token(&user.student_id, user.role as i16)?;
+
+    Ok(HttpResponse::Ok().json({
+        serde_json::json!({
+            "token": token
+        })
+    }))
 }
 
 /// Handles GET requests to retrieve all applicants from the SQLite database
-async fn get_applicants(db_pool: web::Data<SqlitePool>) -> impl Responder {
-    match query_as!(
+#[utoipa::path(
+    get,
+    path = "/applicants",
+    responses(
+        (status = 200, description = "List of applicants", body = [Response]),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 500, description = "Database error")
+    ),
+    security(("bearer_token" = []))
+)]
+async fn get_applicants(user: AuthUser, db_pool: web::Data<SqlitePool>) -> Result<HttpResponse, Error> {
+    user.require_role(auth::ADMIN)?;
+
+    let applicants = query_as!(
         Response,
         r#"
-        SELECT 
-            first_name, 
-            last_name, 
-            student_id, 
-            gender, 
-            dob, 
-            college_year, 
-            total_credits, 
-            phone_number, 
+        SELECT
+            first_name,
+            last_name,
+            student_id,
+            gender,
+            dob,
+            college_year,
+            total_credits,
+            phone_number,
             email,
             role,
-            status, 
+            status,
             gpa
         FROM form_data
         "#
     )
     .fetch_all(db_pool.get_ref())
-    .await 
-    {
-        Ok(applicants) => HttpResponse::Ok().json({
-            serde_json::json!({
-                "status": "success",
-                "data": applicants
-            })
-        }),
-        Err(error) => {
-            eprintln!("Error fetching applicants: {:?}", error);
-            HttpResponse::InternalServerError().json({
-                serde_json::json!({
-                    "status": "error",
-                    "message": "Failed to fetch applicants. Please try again later."
-                })
-            })
-        }
-    }
+    .await?;
+
+    Ok(HttpResponse::Ok().json({
+        serde_json::json!({
+            "status": "success",
+            "data": applicants
+        })
+    }))
 }
 
 #[actix_web::main]
@@ -322,14 +313,25 @@ async fn main() -> std::io::Result<()> {
     println!("Connected to SQLite database at: {}", database_url);
     println!("Starting server on http://127.0.0.1:8000");
 
+    let allowed_origin = env::var("CORS_ALLOWED_ORIGIN")
+        .expect("CORS_ALLOWED_ORIGIN is not set in the .env file. Please configure it.");
+
     HttpServer::new(move || {
+        // A credentialed, cross-origin frontend needs an explicit origin (not
+        // allow_any_origin()) plus supports_credentials() for the CSRF cookie
+        // to actually be sent back, and the CSRF header exposed so JS can read it.
         let cors = Cors::default()
-            .allow_any_origin()
+            .allowed_origin(&allowed_origin)
             .allow_any_method()
-            .allow_any_header();
+            .allow_any_header()
+            .expose_headers(vec!["X-CSRF-Token"])
+            .supports_credentials();
         App::new()
             .app_data(web::Data::new(db_pool.clone()))
             .wrap(cors)
+            .wrap(Csrf::new())
+            .service(SwaggerUi::new("/swagger-ui/{_:.*}").url("/api-docs/openapi.json", ApiDoc::openapi()))
+            .route("/csrf-token", web::get().to(csrf_token))
             .route("/submit", web::post().to(submit_form))
             .route("/login", web::post().to(login)) // New route for login
             .route("/applicants", web::get().to(get_applicants))
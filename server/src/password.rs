@@ -0,0 +1,107 @@
+use argon2::password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Algorithm, Argon2, Params, Version};
+use std::env;
+
+use crate::error::Error;
+
+const BCRYPT_PREFIXES: [&str; 4] = ["$2a$", "$2b$", "$2x$", "$2y$"];
+
+/// Reads Argon2id's cost parameters from the environment so they can be
+/// tuned per deployment, falling back to the crate's recommended defaults.
+fn argon2_params() -> Params {
+    let memory_kib = env::var("ARGON2_MEMORY_KIB")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(19_456);
+    let iterations = env::var("ARGON2_ITERATIONS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(2);
+    let parallelism = env::var("ARGON2_PARALLELISM")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(1);
+
+    Params::new(memory_kib, iterations, parallelism, None).expect("invalid Argon2 parameters")
+}
+
+fn argon2() -> Argon2<'static> {
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, argon2_params())
+}
+
+/// Hashes `password` with Argon2id, returning a PHC string suitable for storage.
+pub fn hash_password(password: &str) -> Result<String, Error> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = argon2().hash_password(password.as_bytes(), &salt)?;
+    Ok(hash.to_string())
+}
+
+/// Outcome of checking a password against its stored hash.
+pub enum Verified {
+    /// The password matched. `rehash` is set when the stored hash was a
+    /// legacy bcrypt hash, so the caller can persist the Argon2id upgrade.
+    Valid { rehash: Option<String> },
+    Invalid,
+}
+
+/// Verifies `password` against `stored_hash`, transparently handling accounts
+/// still on a legacy bcrypt hash by verifying with bcrypt and, on success,
+/// producing a fresh Argon2id hash for the caller to persist.
+pub fn verify_password(password: &str, stored_hash: &str) -> Result<Verified, Error> {
+    if BCRYPT_PREFIXES.iter().any(|prefix| stored_hash.starts_with(prefix)) {
+        return if bcrypt::verify(password, stored_hash)? {
+            Ok(Verified::Valid {
+                rehash: Some(hash_password(password)?),
+            })
+        } else {
+            Ok(Verified::Invalid)
+        };
+    }
+
+    let parsed_hash = PasswordHash::new(stored_hash)?;
+    match argon2().verify_password(password.as_bytes(), &parsed_hash) {
+        Ok(()) => Ok(Verified::Valid { rehash: None }),
+        Err(_) => Ok(Verified::Invalid),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matching_bcrypt_hash_triggers_rehash() {
+        let hash = bcrypt::hash("hunter2", bcrypt::DEFAULT_COST).unwrap();
+
+        let outcome = verify_password("hunter2", &hash).unwrap();
+
+        match outcome {
+            Verified::Valid { rehash } => {
+                let rehash = rehash.expect("legacy bcrypt match should produce an Argon2 rehash");
+                assert!(rehash.starts_with("$argon2id$"));
+            }
+            Verified::Invalid => panic!("expected the bcrypt password to verify"),
+        }
+    }
+
+    #[test]
+    fn matching_argon2_hash_does_not_rehash() {
+        let hash = hash_password("hunter2").unwrap();
+
+        let outcome = verify_password("hunter2", &hash).unwrap();
+
+        match outcome {
+            Verified::Valid { rehash } => assert!(rehash.is_none()),
+            Verified::Invalid => panic!("expected the Argon2 password to verify"),
+        }
+    }
+
+    #[test]
+    fn mismatched_password_is_invalid() {
+        let hash = hash_password("hunter2").unwrap();
+
+        let outcome = verify_password("wrong-password", &hash).unwrap();
+
+        assert!(matches!(outcome, Verified::Invalid));
+    }
+}